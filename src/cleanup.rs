@@ -0,0 +1,75 @@
+//! Cancellation/cleanup of orders placed during a benchmark run, so a
+//! `--iterations N` test doesn't leave N live resting orders on the
+//! book. Tracks every placed order's id, cancels them all after the run
+//! (or immediately on Ctrl-C), and reports cancel latency as its own
+//! stage alongside build/sign/post.
+
+use polymarket_client_sdk::clob::Client;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::stats::Histogram;
+
+/// Shared, thread-safe registry of order ids placed so far this run, so
+/// both the main loop and a Ctrl-C handler can see what needs cancelling.
+#[derive(Clone, Default)]
+pub struct PlacedOrders {
+    ids: Arc<Mutex<Vec<String>>>,
+}
+
+impl PlacedOrders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, order_id: String) {
+        self.ids.lock().unwrap().push(order_id);
+    }
+
+    pub fn snapshot(&self) -> Vec<String> {
+        self.ids.lock().unwrap().clone()
+    }
+}
+
+/// Cancels every order in `order_ids`, recording each cancel's latency.
+/// Individual cancel failures are reported but don't abort the sweep —
+/// best-effort cleanup shouldn't lose track of the rest of the book.
+pub async fn cancel_all(client: &Client, order_ids: &[String]) -> Histogram {
+    let mut cancel_hist = Histogram::new();
+    if order_ids.is_empty() {
+        return cancel_hist;
+    }
+
+    println!("\n🧹 Cancelling {} test order(s)...", order_ids.len());
+    for order_id in order_ids {
+        let start = Instant::now();
+        match client.cancel_order(order_id).await {
+            Ok(_) => {
+                let cancel_us = start.elapsed().as_micros() as f64;
+                cancel_hist.record(cancel_us);
+                println!("   ✅ Cancelled {} ({:.1}ms)", order_id, cancel_us / 1000.0);
+            }
+            Err(e) => {
+                eprintln!("   ⚠️  Failed to cancel {}: {}", order_id, e);
+            }
+        }
+    }
+
+    cancel_hist
+}
+
+/// Spawns a background task that cancels every order in `orders` as
+/// soon as Ctrl-C is received, then exits the process. This is the
+/// safety net for interrupting a long `--iterations` run without
+/// leaving resting orders behind.
+pub fn install_ctrl_c_handler(client: Arc<Client>, orders: PlacedOrders) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        println!("\n🛑 Ctrl-C received, cancelling test orders before exit...");
+        let ids = orders.snapshot();
+        cancel_all(&client, &ids).await;
+        std::process::exit(130);
+    });
+}
@@ -0,0 +1,80 @@
+//! Optional fill-tracking mode: places marketable orders and polls order
+//! status until the first fill, measuring POST-to-fill latency — the
+//! metric that reflects execution quality for a trading strategy, not
+//! just POST acknowledgement time.
+
+use anyhow::Result;
+use polymarket_client_sdk::clob::Client;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Status the CLOB API reports once an order has actually matched
+/// (fully or partially). This is the only status that counts as a fill.
+const STATUS_MATCHED: &str = "MATCHED";
+
+/// Terminal statuses that mean the order is done but never filled —
+/// these must stop polling without reporting a fill latency, since
+/// "left the LIVE state" alone doesn't imply a match happened.
+const TERMINAL_UNFILLED_STATUSES: &[&str] = &["CANCELED", "CANCELLED", "REJECTED", "EXPIRED"];
+
+/// How often to poll order status while waiting for a fill.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for a fill before giving up on this order.
+const POLL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Polls `order_id`'s status until it matches (`"MATCHED"`), reaches a
+/// terminal unfilled state (cancelled/rejected/expired), or
+/// `POLL_TIMEOUT` elapses. Returns the POST-to-fill latency in
+/// microseconds on a match, or `None` if the order never filled.
+pub async fn wait_for_fill(client: &Client, order_id: &str) -> Result<Option<f64>> {
+    let start = Instant::now();
+    loop {
+        let order = client.order(order_id).await?;
+        if order.status == STATUS_MATCHED {
+            return Ok(Some(start.elapsed().as_micros() as f64));
+        }
+        if TERMINAL_UNFILLED_STATUSES.contains(&order.status.as_str()) {
+            return Ok(None);
+        }
+        if start.elapsed() > POLL_TIMEOUT {
+            return Ok(None);
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Nudges a resting limit price to be marketable so a fill-tracking run
+/// actually crosses the spread instead of resting indefinitely: buys are
+/// pushed up, sells pushed down, by `pct` (e.g. 0.01 for 1%).
+pub fn marketable_price(price: f64, side_is_buy: bool, pct: f64) -> f64 {
+    let nudged = if side_is_buy {
+        price * (1.0 + pct)
+    } else {
+        price * (1.0 - pct)
+    };
+    nudged.clamp(0.01, 0.99)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marketable_price_nudges_buy_up() {
+        let price = marketable_price(0.50, true, 0.01);
+        assert!((price - 0.505).abs() < 1e-9);
+    }
+
+    #[test]
+    fn marketable_price_nudges_sell_down() {
+        let price = marketable_price(0.50, false, 0.01);
+        assert!((price - 0.495).abs() < 1e-9);
+    }
+
+    #[test]
+    fn marketable_price_clamps_to_valid_range() {
+        assert_eq!(marketable_price(0.999, true, 0.5), 0.99);
+        assert_eq!(marketable_price(0.001, false, 0.5), 0.01);
+    }
+}
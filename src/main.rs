@@ -1,8 +1,25 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use dotenv::dotenv;
-use std::{env, str::FromStr, time::Instant};
+use std::{
+    env,
+    str::FromStr,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 use tokio::time::{sleep, Duration};
+use uuid::Uuid;
+
+mod cleanup;
+mod fills;
+mod market;
+mod ping;
+mod stats;
+mod storage;
+mod throughput;
+use cleanup::PlacedOrders;
+use market::PriceMode;
+use stats::Histogram;
+use storage::{OrderRecord, Storage};
 
 // SDK Imports
 use polymarket_client_sdk::clob::types::{OrderType, Side, SignatureType};
@@ -15,7 +32,21 @@ use alloy::signers::local::PrivateKeySigner;
 use alloy::signers::Signer;
 
 #[derive(Parser, Debug)]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fire signed orders against a host and report placement latency.
+    Bench(BenchArgs),
+    /// No-order RTT probe across candidate hosts, for VPS placement.
+    Ping(PingArgs),
+}
+
+#[derive(Parser, Debug)]
+struct BenchArgs {
     #[arg(long)]
     token_id: String,
     #[arg(long)]
@@ -26,13 +57,64 @@ struct Args {
     side: String,
     #[arg(long, default_value = "3")]
     iterations: usize,
+    /// Number of orders to keep in flight at once. 1 (default) preserves
+    /// the original strictly-sequential one-order-then-sleep behavior;
+    /// >1 switches to throughput mode.
+    #[arg(long, default_value = "1")]
+    concurrency: usize,
+    /// In throughput mode (concurrency > 1), run for this many seconds
+    /// instead of for a fixed `--iterations` count.
+    #[arg(long)]
+    duration_secs: Option<u64>,
+    /// Skip cancelling test orders after the run. By default every
+    /// order placed during the benchmark is cancelled once it
+    /// completes (or immediately on Ctrl-C) so the test doesn't leave
+    /// resting orders on the book.
+    #[arg(long)]
+    no_cleanup: bool,
+    /// Also measure end-to-end POST-to-first-fill latency. Nudges
+    /// `--price` to be marketable and polls order status until it
+    /// matches. Sequential mode (`--concurrency 1`) only.
+    #[arg(long)]
+    track_fills: bool,
+    /// How to derive the test price: `fixed` (use `--price` as-is),
+    /// `best-bid`, `best-ask`, `mid`, or `cross+Nticks` to aggressively
+    /// cross the spread by N ticks (e.g. `cross+2ticks`). Any mode other
+    /// than `fixed` fetches the live order book before each order.
+    #[arg(long, default_value = "fixed")]
+    price_mode: String,
+    /// Order book depth (number of levels) to fetch alongside best
+    /// bid/ask when `--price-mode` isn't `fixed`.
+    #[arg(long, default_value = "1")]
+    levels: usize,
+}
+
+#[derive(Parser, Debug)]
+struct PingArgs {
+    /// Candidate CLOB hostnames/regions to test, e.g.
+    /// `--hosts https://clob.polymarket.com https://clob-eu.polymarket.com`
+    #[arg(long, required = true, num_args = 1..)]
+    hosts: Vec<String>,
+    #[arg(long, default_value = "10")]
+    samples: usize,
+    #[arg(long, default_value = "100")]
+    interval_ms: u64,
 }
 
-#[tokio::main]
+// Enough worker threads that a slow Postgres write can never stall the
+// order-placement loop on the same scheduler it runs on.
+#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() -> Result<()> {
     dotenv().ok();
-    let args = Args::parse();
+    let cli = Cli::parse();
 
+    match cli.command {
+        Command::Bench(args) => run_bench(args).await,
+        Command::Ping(args) => ping::run(&args.hosts, args.samples, args.interval_ms).await,
+    }
+}
+
+async fn run_bench(args: BenchArgs) -> Result<()> {
     let pk_str = env::var("PK").expect("PK missing in .env");
     let chain_id = 137;
     let host = "https://clob.polymarket.com";
@@ -55,6 +137,17 @@ async fn main() -> Result<()> {
         .signature_type(SignatureType::GnosisSafe)
         .authenticate()
         .await?;
+    let client = std::sync::Arc::new(client);
+    let signer = std::sync::Arc::new(signer);
+
+    // Track every order we place so it can be cancelled after the run,
+    // or immediately if the user hits Ctrl-C mid-benchmark. Respects
+    // --no-cleanup: if the user opted out of cleanup, Ctrl-C shouldn't
+    // cancel orders behind their back either.
+    let placed_orders = PlacedOrders::new();
+    if !args.no_cleanup {
+        cleanup::install_ctrl_c_handler(client.clone(), placed_orders.clone());
+    }
 
     // Pre-parse parameters (do once, reuse for all orders)
     let side = if args.side.to_uppercase() == "SELL" {
@@ -63,9 +156,37 @@ async fn main() -> Result<()> {
         Side::Buy
     };
 
-    let price_d = Decimal::from_str(&format!("{:.2}", args.price))?;
+    let price_mode: PriceMode = args.price_mode.parse()?;
+    // --track-fills nudges --price to be marketable, but a book-derived
+    // price mode would silently discard that nudge (compute_price never
+    // reads fixed_price except for PriceMode::Fixed), so reject the
+    // combination up front instead of printing a nudge that never applies.
+    if args.track_fills && !matches!(price_mode, PriceMode::Fixed) {
+        anyhow::bail!(
+            "--track-fills only supports --price-mode fixed; a book-derived price mode \
+             would discard the fill-tracking nudge applied to --price"
+        );
+    }
+
+    let price = if args.track_fills && args.concurrency <= 1 {
+        let nudged = fills::marketable_price(args.price, matches!(side, Side::Buy), 0.01);
+        println!(
+            "🎯 --track-fills: nudging price {:.2} -> {:.2} to cross the book",
+            args.price, nudged
+        );
+        nudged
+    } else {
+        args.price
+    };
+    let price_d = Decimal::from_str(&format!("{:.2}", price))?;
     let size_d = Decimal::from_str(&args.size.to_string())?;
     let token_id_u256 = U256::from_str_radix(&args.token_id, 10)?;
+    if !matches!(price_mode, PriceMode::Fixed) {
+        println!(
+            "📈 Price mode: {} (book depth: {} level(s))",
+            args.price_mode, args.levels
+        );
+    }
 
     // ⚡ Pre-warm caches in parallel (one-time cost)
     let (tick_res, neg_res, fee_res) = tokio::join!(
@@ -74,106 +195,225 @@ async fn main() -> Result<()> {
         client.fee_rate_bps(token_id_u256)
     );
 
-    tick_res?;
+    let tick_size = tick_res?;
     neg_res?;
     fee_res?;
 
     let setup_time = setup_start.elapsed().as_millis();
     println!("✅ Setup complete: {}ms (one-time cost)", setup_time);
+
+    // Optional Postgres/TimescaleDB persistence; a no-op if PG_URL isn't set.
+    let run_id = Uuid::new_v4();
+    let storage = Storage::connect(run_id).await?.map(std::sync::Arc::new);
+    if storage.is_some() {
+        println!("🗄️  Persisting measurements to Postgres (run_id={})", run_id);
+    }
     println!("---------------------------------------------------\n");
 
-    // Track statistics
-    let mut total_latencies = Vec::new();
-    let mut build_times = Vec::new();
-    let mut sign_times = Vec::new();
-    let mut post_times = Vec::new();
-
-    // --- SIMULATE LIVE TRADING: Orders sent one-by-one ---
-    for i in 1..=args.iterations {
-        println!("🚀 Order #{} (live execution)...", i);
-
-        let order_start = Instant::now();
-
-        // BUILD ORDER
-        let build_start = Instant::now();
-        let limit_order = client
-            .limit_order()
-            .token_id(token_id_u256)
-            .price(price_d)
-            .size(size_d)
-            .side(side)
-            .order_type(OrderType::GTC)
-            .build()
-            .await?;
-        let build_ms = build_start.elapsed().as_millis();
-
-        // SIGN ORDER
-        let sign_start = Instant::now();
-        let signed_order = client.sign(&signer, limit_order).await?;
-        let sign_ms = sign_start.elapsed().as_millis();
-
-        // POST ORDER
-        let post_start = Instant::now();
-        let response = client.post_order(signed_order).await?;
-        let post_ms = post_start.elapsed().as_millis();
-
-        let total_ms = order_start.elapsed().as_millis();
-
-        // Record stats
-        total_latencies.push(total_ms);
-        build_times.push(build_ms);
-        sign_times.push(sign_ms);
-        post_times.push(post_ms);
-
-        println!("✅ Order #{} posted", i);
-        println!("⏱️  Total: {}ms", total_ms);
-        println!("   ├─ Build: {}ms (cached metadata)", build_ms);
-        println!("   ├─ Sign:  {}ms (crypto)", sign_ms);
-        println!("   └─ POST:  {}ms (network)", post_ms);
-        println!("🆔 {}", response.order_id);
-
-        if i < args.iterations {
-            println!("... Sleeping 1s ...\n");
-            sleep(Duration::from_millis(1000)).await;
+    // Track statistics (recorded in microseconds so sub-ms build/sign
+    // stages are still visible alongside network-bound POST latency)
+    let total_hist;
+    let build_hist;
+    let sign_hist;
+    let post_hist;
+
+    if args.concurrency > 1 {
+        // --- THROUGHPUT MODE: N orders kept in flight at once ---
+        if args.track_fills {
+            println!("⚠️  --track-fills is not supported in throughput mode; ignoring");
+        }
+        let target = match args.duration_secs {
+            Some(secs) => throughput::Target::Duration(Duration::from_secs(secs)),
+            None => throughput::Target::Count(args.iterations),
+        };
+        let (t, b, s, p) = throughput::run(
+            client.clone(),
+            signer.clone(),
+            token_id_u256,
+            price_d,
+            size_d,
+            side,
+            host,
+            args.concurrency,
+            target,
+            storage.clone(),
+            placed_orders.clone(),
+            price_mode,
+            tick_size,
+            args.levels,
+        )
+        .await?;
+        total_hist = t;
+        build_hist = b;
+        sign_hist = s;
+        post_hist = p;
+    } else {
+        // --- SIMULATE LIVE TRADING: Orders sent one-by-one ---
+        let mut total = Histogram::new();
+        let mut build = Histogram::new();
+        let mut sign = Histogram::new();
+        let mut post = Histogram::new();
+        let mut fill_hist = Histogram::new();
+        let mut book_hist = Histogram::new();
+        let mut fill_timeouts = 0usize;
+
+        for i in 1..=args.iterations {
+            println!("🚀 Order #{} (live execution)...", i);
+
+            // Run this iteration's book-fetch/build/sign/post/fill-wait as
+            // one fallible unit: a transient error (e.g. a dropped
+            // connection mid-poll) must not `?`-propagate out of
+            // `run_bench`, since that would skip the cancellation and
+            // storage.finish() stages below and leave earlier iterations'
+            // orders resting live with no summary row ever written.
+            let iteration: Result<()> = async {
+                let order_start = Instant::now();
+
+                // FETCH BOOK (only when the price depends on live top-of-book)
+                let order_price = if let PriceMode::Fixed = price_mode {
+                    price_d
+                } else {
+                    let book_start = Instant::now();
+                    let book = market::fetch_book(&client, token_id_u256, args.levels).await?;
+                    book_hist.record(book_start.elapsed().as_micros() as f64);
+                    market::compute_price(price_mode, &book, side, price_d, tick_size)
+                };
+
+                // BUILD ORDER
+                let build_start = Instant::now();
+                let limit_order = client
+                    .limit_order()
+                    .token_id(token_id_u256)
+                    .price(order_price)
+                    .size(size_d)
+                    .side(side)
+                    .order_type(OrderType::GTC)
+                    .build()
+                    .await?;
+                let build_us = build_start.elapsed().as_micros() as f64;
+
+                // SIGN ORDER
+                let sign_start = Instant::now();
+                let signed_order = client.sign(&signer, limit_order).await?;
+                let sign_us = sign_start.elapsed().as_micros() as f64;
+
+                // POST ORDER
+                let post_start = Instant::now();
+                let response = client.post_order(signed_order).await?;
+                let post_us = post_start.elapsed().as_micros() as f64;
+
+                let total_us = order_start.elapsed().as_micros() as f64;
+
+                placed_orders.push(response.order_id.clone());
+
+                // Record stats (skip warmup order below if iterations > 1)
+                if i > 1 || args.iterations == 1 {
+                    total.record(total_us);
+                    build.record(build_us);
+                    sign.record(sign_us);
+                    post.record(post_us);
+                }
+
+                if let Some(storage) = &storage {
+                    let timestamp_ms = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_millis() as i64)
+                        .unwrap_or(0);
+                    storage.record_order(OrderRecord {
+                        run_id,
+                        timestamp_ms,
+                        host: host.to_string(),
+                        token_id: args.token_id.clone(),
+                        side: args.side.clone(),
+                        build_ms: build_us / 1000.0,
+                        sign_ms: sign_us / 1000.0,
+                        post_ms: post_us / 1000.0,
+                        total_ms: total_us / 1000.0,
+                    });
+                }
+
+                println!("✅ Order #{} posted", i);
+                println!("⏱️  Total: {:.1}ms", total_us / 1000.0);
+                println!("   ├─ Build: {:.1}ms (cached metadata)", build_us / 1000.0);
+                println!("   ├─ Sign:  {:.1}ms (crypto)", sign_us / 1000.0);
+                println!("   └─ POST:  {:.1}ms (network)", post_us / 1000.0);
+                println!("🆔 {}", response.order_id);
+
+                if args.track_fills {
+                    match fills::wait_for_fill(&client, &response.order_id).await? {
+                        Some(fill_us) => {
+                            fill_hist.record(fill_us);
+                            println!("   🎯 First fill: {:.1}ms after POST", fill_us / 1000.0);
+                        }
+                        None => {
+                            fill_timeouts += 1;
+                            println!("   ⌛ No fill observed within timeout");
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = iteration {
+                eprintln!(
+                    "⚠️  Order #{} failed: {} — stopping the run early so cleanup/storage still run",
+                    i, e
+                );
+                break;
+            }
+
+            if i < args.iterations {
+                println!("... Sleeping 1s ...\n");
+                sleep(Duration::from_millis(1000)).await;
+            }
+        }
+
+        if book_hist.count() > 0 {
+            println!();
+            book_hist.print_summary("Book Fetch Latency");
+        }
+
+        if args.track_fills && fill_hist.count() > 0 {
+            println!();
+            fill_hist.print_summary("POST-to-Fill Latency");
+            if fill_timeouts > 0 {
+                println!("  ({} order(s) did not fill within timeout)", fill_timeouts);
+            }
         }
-    }
 
-    // Print statistics (excluding first order if warmup needed)
-    let skip_first = if args.iterations > 1 { 1 } else { 0 };
+        total_hist = total;
+        build_hist = build;
+        sign_hist = sign;
+        post_hist = post;
+    }
 
+    // Print statistics (sequential mode already excluded the warmup order
+    // from the histograms; throughput mode records every order)
     println!("\n═══════════════════════════════════════════════════");
     println!("📊 PERFORMANCE STATISTICS (excluding setup)");
     println!("═══════════════════════════════════════════════════");
 
-    if args.iterations > skip_first {
-        let steady_state: Vec<_> = total_latencies.iter().skip(skip_first).copied().collect();
-        let avg = steady_state.iter().sum::<u128>() / steady_state.len() as u128;
-        let min = steady_state.iter().min().unwrap();
-        let max = steady_state.iter().max().unwrap();
-
-        println!("Total Latency:");
-        println!("  Average: {}ms", avg);
-        println!("  Min:     {}ms", min);
-        println!("  Max:     {}ms", max);
-
-        let avg_build = build_times.iter().skip(skip_first).sum::<u128>()
-            / (args.iterations - skip_first) as u128;
-        let avg_sign = sign_times.iter().skip(skip_first).sum::<u128>()
-            / (args.iterations - skip_first) as u128;
-        let avg_post = post_times.iter().skip(skip_first).sum::<u128>()
-            / (args.iterations - skip_first) as u128;
-
-        println!("\nBreakdown (avg):");
-        println!("  Build: {}ms", avg_build);
-        println!("  Sign:  {}ms", avg_sign);
-        println!("  POST:  {}ms", avg_post);
-
-        // Identify bottleneck
+    if total_hist.count() > 0 {
+        let suffix = if args.concurrency > 1 { " (under load)" } else { "" };
+        total_hist.print_summary(&format!("Total Latency{}", suffix));
+        println!();
+        build_hist.print_summary(&format!("Build{}", suffix));
+        println!();
+        sign_hist.print_summary(&format!("Sign{}", suffix));
+        println!();
+        post_hist.print_summary(&format!("POST{}", suffix));
+
+        // Identify bottleneck using p50 as the representative value
+        let p50_sign = sign_hist.quantile(0.50);
+        let p50_post = post_hist.quantile(0.50);
+
         println!("\n🎯 Bottleneck Analysis:");
-        if avg_post > avg_sign * 2 {
+        if p50_post > p50_sign * 2.0 {
             println!("   Network (POST) is the limiting factor");
             println!("   → Consider VPS closer to Polymarket servers");
-        } else if avg_sign > avg_post {
+        } else if p50_sign > p50_post {
             println!("   Crypto (Sign) is the limiting factor");
             println!("   → Already optimized; consider release build");
         } else {
@@ -183,5 +423,25 @@ async fn main() -> Result<()> {
 
     println!("═══════════════════════════════════════════════════\n");
 
+    if !args.no_cleanup {
+        let order_ids = placed_orders.snapshot();
+        let cancel_hist = cleanup::cancel_all(&client, &order_ids).await;
+        if cancel_hist.count() > 0 {
+            println!();
+            cancel_hist.print_summary("Cancel Latency");
+        }
+    }
+
+    if let Some(storage) = storage.and_then(|s| std::sync::Arc::try_unwrap(s).ok()) {
+        storage
+            .finish(
+                host,
+                total_hist.count() as usize,
+                total_hist.quantile(0.50) / 1000.0,
+                total_hist.quantile(0.99) / 1000.0,
+            )
+            .await?;
+    }
+
     Ok(())
 }
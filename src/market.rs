@@ -0,0 +1,215 @@
+//! Order-book-aware pricing: fetches live top-of-book before placing a
+//! test order so a moving market doesn't leave the test price far from
+//! executable, which would make POST latency measurements unrepresentative
+//! of a real placement.
+
+use anyhow::{bail, Result};
+use polymarket_client_sdk::clob::types::Side;
+use polymarket_client_sdk::clob::Client;
+use polymarket_client_sdk::types::Decimal;
+use std::str::FromStr;
+
+use alloy::primitives::U256;
+
+/// The top `levels` bid/ask rungs of a book fetch, best-first.
+pub struct BookSnapshot {
+    pub bids: Vec<Decimal>,
+    pub asks: Vec<Decimal>,
+}
+
+impl BookSnapshot {
+    /// Best (highest) bid.
+    pub fn best_bid(&self) -> Decimal {
+        self.bids[0]
+    }
+
+    /// Best (lowest) ask.
+    pub fn best_ask(&self) -> Decimal {
+        self.asks[0]
+    }
+
+    /// Midpoint between best bid and best ask.
+    pub fn mid(&self) -> Decimal {
+        (self.best_bid() + self.best_ask()) / Decimal::from(2)
+    }
+
+    /// How many levels were actually fetched on the thinner side of the
+    /// book (may be less than the requested `levels` near the edges of
+    /// the book).
+    pub fn depth(&self) -> usize {
+        self.bids.len().min(self.asks.len())
+    }
+}
+
+/// How the test price for an order should be derived.
+#[derive(Debug, Clone, Copy)]
+pub enum PriceMode {
+    /// Use the `--price` the user passed, unchanged.
+    Fixed,
+    /// Use the current best bid.
+    BestBid,
+    /// Use the current best ask.
+    BestAsk,
+    /// Use the midpoint of best bid/ask.
+    Mid,
+    /// Cross the spread by `n` ticks past the opposite side, so the
+    /// order is aggressively marketable (e.g. `cross+2` on a buy takes
+    /// best ask + 2 ticks).
+    Cross(u32),
+}
+
+impl FromStr for PriceMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "fixed" => Ok(PriceMode::Fixed),
+            "best-bid" => Ok(PriceMode::BestBid),
+            "best-ask" => Ok(PriceMode::BestAsk),
+            "mid" => Ok(PriceMode::Mid),
+            _ => {
+                if let Some(n_str) = s.strip_prefix("cross+") {
+                    let n: u32 = n_str
+                        .strip_suffix("ticks")
+                        .unwrap_or(n_str)
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("invalid --price-mode '{}'", s))?;
+                    Ok(PriceMode::Cross(n))
+                } else {
+                    bail!(
+                        "invalid --price-mode '{}': expected fixed, best-bid, best-ask, mid, or cross+Nticks",
+                        s
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Fetches the order book for `token_id` and returns the top `levels`
+/// bid/ask rungs (best-first).
+pub async fn fetch_book(client: &Client, token_id: U256, levels: usize) -> Result<BookSnapshot> {
+    let levels = levels.max(1);
+    let book = client.book(token_id).await?;
+
+    let bids: Vec<Decimal> = book.bids.iter().take(levels).map(|l| l.price).collect();
+    let asks: Vec<Decimal> = book.asks.iter().take(levels).map(|l| l.price).collect();
+
+    if bids.is_empty() {
+        bail!("order book has no bids");
+    }
+    if asks.is_empty() {
+        bail!("order book has no asks");
+    }
+
+    Ok(BookSnapshot { bids, asks })
+}
+
+/// Snaps `price` to the nearest multiple of `tick_size`.
+fn snap_to_tick(price: Decimal, tick_size: Decimal) -> Decimal {
+    if tick_size.is_zero() {
+        return price;
+    }
+    (price / tick_size).round() * tick_size
+}
+
+/// Derives the test price for `side` from `mode`, the live `book`, and
+/// the token's `tick_size`. `fixed_price` is used as-is for
+/// [`PriceMode::Fixed`].
+pub fn compute_price(
+    mode: PriceMode,
+    book: &BookSnapshot,
+    side: Side,
+    fixed_price: Decimal,
+    tick_size: Decimal,
+) -> Decimal {
+    let raw = match mode {
+        PriceMode::Fixed => return fixed_price,
+        PriceMode::BestBid => book.best_bid(),
+        PriceMode::BestAsk => book.best_ask(),
+        PriceMode::Mid => book.mid(),
+        PriceMode::Cross(n) => {
+            let ticks = tick_size * Decimal::from(n);
+            match side {
+                Side::Buy => book.best_ask() + ticks,
+                Side::Sell => book.best_bid() - ticks,
+            }
+        }
+    };
+    snap_to_tick(raw, tick_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn book(bid: &str, ask: &str) -> BookSnapshot {
+        BookSnapshot {
+            bids: vec![dec(bid)],
+            asks: vec![dec(ask)],
+        }
+    }
+
+    #[test]
+    fn snap_to_tick_rounds_to_nearest_multiple() {
+        assert_eq!(snap_to_tick(dec("0.473"), dec("0.01")), dec("0.47"));
+        assert_eq!(snap_to_tick(dec("0.475"), dec("0.01")), dec("0.48"));
+        assert_eq!(snap_to_tick(dec("0.476"), dec("0.01")), dec("0.48"));
+    }
+
+    #[test]
+    fn snap_to_tick_zero_tick_size_is_noop() {
+        assert_eq!(snap_to_tick(dec("0.4731"), dec("0")), dec("0.4731"));
+    }
+
+    #[test]
+    fn compute_price_fixed_ignores_book() {
+        let book = book("0.40", "0.42");
+        let price = compute_price(PriceMode::Fixed, &book, Side::Buy, dec("0.5"), dec("0.01"));
+        assert_eq!(price, dec("0.5"));
+    }
+
+    #[test]
+    fn compute_price_best_bid_and_ask() {
+        let book = book("0.40", "0.42");
+        assert_eq!(
+            compute_price(PriceMode::BestBid, &book, Side::Buy, dec("0.5"), dec("0.01")),
+            dec("0.40")
+        );
+        assert_eq!(
+            compute_price(PriceMode::BestAsk, &book, Side::Sell, dec("0.5"), dec("0.01")),
+            dec("0.42")
+        );
+    }
+
+    #[test]
+    fn compute_price_mid_snaps_to_tick() {
+        let book = book("0.40", "0.43");
+        // Midpoint is 0.415, exactly between two ticks; the tie rounds to 0.42.
+        let price = compute_price(PriceMode::Mid, &book, Side::Buy, dec("0.5"), dec("0.01"));
+        assert_eq!(price, dec("0.42"));
+    }
+
+    #[test]
+    fn compute_price_cross_moves_past_opposite_side() {
+        let book = book("0.40", "0.42");
+        let buy = compute_price(PriceMode::Cross(2), &book, Side::Buy, dec("0.5"), dec("0.01"));
+        assert_eq!(buy, dec("0.44"));
+        let sell = compute_price(PriceMode::Cross(2), &book, Side::Sell, dec("0.5"), dec("0.01"));
+        assert_eq!(sell, dec("0.38"));
+    }
+
+    #[test]
+    fn price_mode_from_str_parses_all_variants() {
+        assert!(matches!("fixed".parse::<PriceMode>().unwrap(), PriceMode::Fixed));
+        assert!(matches!("best-bid".parse::<PriceMode>().unwrap(), PriceMode::BestBid));
+        assert!(matches!("best-ask".parse::<PriceMode>().unwrap(), PriceMode::BestAsk));
+        assert!(matches!("mid".parse::<PriceMode>().unwrap(), PriceMode::Mid));
+        assert!(matches!("cross+2ticks".parse::<PriceMode>().unwrap(), PriceMode::Cross(2)));
+        assert!("bogus".parse::<PriceMode>().is_err());
+    }
+}
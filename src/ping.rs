@@ -0,0 +1,181 @@
+//! No-order "ping" mode: ranks candidate CLOB hosts by round-trip time so
+//! a VPS region can be chosen before any capital is put at risk with a
+//! signed order.
+
+use anyhow::Result;
+use polymarket_client_sdk::clob::{Client, Config};
+use std::time::Instant;
+use tokio::time::{sleep, timeout, Duration};
+
+/// How long to wait for a single `server_time()` request before counting
+/// it as an error. Without this, a host that hangs (rather than erroring)
+/// would block `ping_host` forever, and since hosts are pinged
+/// sequentially, one unresponsive candidate would hang the whole command.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One ranked result row: a host and its RTT statistics, in milliseconds.
+struct HostResult {
+    host: String,
+    min_ms: f64,
+    mean_ms: f64,
+    p95_ms: f64,
+    stddev_ms: f64,
+    errors: usize,
+}
+
+/// Pings a single host: one untimed warmup request (discarded), then
+/// `samples` timed requests, waiting `interval_ms` between them. If the
+/// client can't be built or every timed request fails, the result has
+/// `errors == samples` and all RTT statistics set to zero.
+async fn ping_host(host: &str, samples: usize, interval_ms: u64) -> HostResult {
+    let client = match Client::new(host, Config::builder().use_server_time(false).build()) {
+        Ok(c) => c,
+        Err(_) => {
+            return HostResult {
+                host: host.to_string(),
+                min_ms: 0.0,
+                mean_ms: 0.0,
+                p95_ms: 0.0,
+                stddev_ms: 0.0,
+                errors: samples,
+            }
+        }
+    };
+
+    let mut rtts_ms = Vec::with_capacity(samples);
+    let mut errors = 0usize;
+
+    // Warm one connection first; this sample is discarded since it
+    // includes TLS/TCP handshake cost that steady-state requests won't pay.
+    let _ = timeout(REQUEST_TIMEOUT, client.server_time()).await;
+
+    for i in 0..samples {
+        let start = Instant::now();
+        match timeout(REQUEST_TIMEOUT, client.server_time()).await {
+            Ok(Ok(_)) => rtts_ms.push(start.elapsed().as_secs_f64() * 1000.0),
+            Ok(Err(_)) | Err(_) => errors += 1,
+        }
+
+        if i + 1 < samples {
+            sleep(Duration::from_millis(interval_ms)).await;
+        }
+    }
+
+    if rtts_ms.is_empty() {
+        return HostResult {
+            host: host.to_string(),
+            min_ms: 0.0,
+            mean_ms: 0.0,
+            p95_ms: 0.0,
+            stddev_ms: 0.0,
+            errors,
+        };
+    }
+
+    let (min_ms, mean_ms, p95_ms, stddev_ms) = compute_stats(&rtts_ms);
+
+    HostResult {
+        host: host.to_string(),
+        min_ms,
+        mean_ms,
+        p95_ms,
+        stddev_ms,
+        errors,
+    }
+}
+
+/// Computes min/mean/p95/stddev from a set of RTT samples (milliseconds).
+/// Pure so it can be unit tested without a live host; `rtts_ms` must be
+/// non-empty.
+fn compute_stats(rtts_ms: &[f64]) -> (f64, f64, f64, f64) {
+    let n = rtts_ms.len();
+    let min_ms = rtts_ms.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mean_ms = rtts_ms.iter().sum::<f64>() / n as f64;
+    let variance = rtts_ms.iter().map(|v| (v - mean_ms).powi(2)).sum::<f64>() / n as f64;
+    let stddev_ms = variance.sqrt();
+
+    let mut sorted = rtts_ms.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let p95_idx = ((0.95 * n as f64).ceil() as usize).saturating_sub(1).min(n - 1);
+    let p95_ms = sorted[p95_idx];
+
+    (min_ms, mean_ms, p95_ms, stddev_ms)
+}
+
+/// Pings every host in `hosts`, `samples` times each, and prints a table
+/// ranked by mean RTT recommending the lowest-latency endpoint.
+pub async fn run(hosts: &[String], samples: usize, interval_ms: u64) -> Result<()> {
+    println!("\n--- 📡 Ping Mode (no orders placed) ---");
+    println!("Testing {} host(s), {} samples each\n", hosts.len(), samples);
+
+    let mut results = Vec::with_capacity(hosts.len());
+    for host in hosts {
+        println!("Pinging {}...", host);
+        results.push(ping_host(host, samples, interval_ms).await);
+    }
+
+    results.sort_by(|a, b| a.mean_ms.partial_cmp(&b.mean_ms).unwrap());
+
+    println!("\n═══════════════════════════════════════════════════");
+    println!("📊 HOST RANKING (by mean RTT)");
+    println!("═══════════════════════════════════════════════════");
+    println!(
+        "{:<40} {:>8} {:>8} {:>8} {:>8} {:>7}",
+        "Host", "min", "mean", "p95", "stddev", "errors"
+    );
+    for r in &results {
+        if r.errors == samples {
+            println!("{:<40} {:>8}", r.host, "FAILED");
+            continue;
+        }
+        println!(
+            "{:<40} {:>6.1}ms {:>6.1}ms {:>6.1}ms {:>6.1}ms {:>7}",
+            r.host, r.min_ms, r.mean_ms, r.p95_ms, r.stddev_ms, r.errors
+        );
+    }
+
+    if let Some(best) = results.iter().find(|r| r.errors < samples) {
+        println!(
+            "\n🏆 Recommended: {} ({:.1}ms mean, {:.1}ms p95)",
+            best.host, best.mean_ms, best.p95_ms
+        );
+    } else {
+        println!("\n⚠️  All hosts failed or timed out");
+    }
+    println!("═══════════════════════════════════════════════════\n");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_stats_single_sample_has_zero_stddev() {
+        let (min, mean, p95, stddev) = compute_stats(&[10.0]);
+        assert_eq!(min, 10.0);
+        assert_eq!(mean, 10.0);
+        assert_eq!(p95, 10.0);
+        assert_eq!(stddev, 0.0);
+    }
+
+    #[test]
+    fn compute_stats_matches_hand_computed_values() {
+        let samples = [10.0, 20.0, 30.0, 40.0, 50.0];
+        let (min, mean, p95, stddev) = compute_stats(&samples);
+        assert_eq!(min, 10.0);
+        assert_eq!(mean, 30.0);
+        // p95 index: ceil(0.95 * 5) - 1 = 4 -> the largest sample.
+        assert_eq!(p95, 50.0);
+        let expected_variance =
+            samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!((stddev - expected_variance.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_stats_p95_index_clamps_to_last_sample() {
+        let (_, _, p95, _) = compute_stats(&[1.0, 2.0]);
+        assert_eq!(p95, 2.0);
+    }
+}
@@ -0,0 +1,194 @@
+//! Fixed-range logarithmic histogram for recording latency samples and
+//! reporting percentiles. Samples are recorded in microseconds so that
+//! sub-millisecond stages (build/sign) remain visible alongside
+//! network-bound stages (POST) that can run into the hundreds of
+//! milliseconds.
+
+/// Lower bound of the histogram range, in microseconds (1µs).
+const MIN_US: f64 = 1.0;
+/// Upper bound of the histogram range, in microseconds (60s).
+const MAX_US: f64 = 60_000_000.0;
+/// Number of buckets spanning the log range.
+const NUM_BUCKETS: usize = 2048;
+
+/// A fixed-range logarithmic histogram over latency samples expressed in
+/// microseconds.
+///
+/// Bucket boundaries are evenly spaced in log-space between `MIN_US` and
+/// `MAX_US`, which gives good resolution across the many orders of
+/// magnitude a latency measurement can span (microsecond signing vs.
+/// multi-second network hiccups) without needing a dynamically sized
+/// histogram.
+pub struct Histogram {
+    buckets: Vec<u64>,
+    count: u64,
+    min_us: f64,
+    max_us: f64,
+    log_min: f64,
+    log_range: f64,
+}
+
+impl Histogram {
+    /// Creates an empty histogram over the default [`MIN_US`, `MAX_US`] range.
+    pub fn new() -> Self {
+        let log_min = MIN_US.ln();
+        let log_range = MAX_US.ln() - log_min;
+        Self {
+            buckets: vec![0; NUM_BUCKETS],
+            count: 0,
+            min_us: MIN_US,
+            max_us: MAX_US,
+            log_min,
+            log_range,
+        }
+    }
+
+    /// Maps a value in microseconds to its bucket index, clamped to the
+    /// configured range.
+    fn bucket_index(&self, value_us: f64) -> usize {
+        let clamped = value_us.clamp(self.min_us, self.max_us);
+        let frac = (clamped.ln() - self.log_min) / self.log_range;
+        let idx = (frac * NUM_BUCKETS as f64) as usize;
+        idx.min(NUM_BUCKETS - 1)
+    }
+
+    /// Maps a bucket index back to its representative value in
+    /// microseconds (the upper edge of the bucket's log-space range).
+    fn bucket_value(&self, idx: usize) -> f64 {
+        let frac = (idx + 1) as f64 / NUM_BUCKETS as f64;
+        (self.log_min + frac * self.log_range).exp()
+    }
+
+    /// Records a single latency sample, in microseconds.
+    pub fn record(&mut self, value_us: f64) {
+        let idx = self.bucket_index(value_us);
+        self.buckets[idx] += 1;
+        self.count += 1;
+    }
+
+    /// Total number of samples recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the value (in microseconds) at quantile `q` (0.0..=1.0),
+    /// i.e. the smallest bucket's representative value such that the
+    /// cumulative fraction of samples at or below it is >= `q`.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let target = (q * self.count as f64).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (idx, &bucket_count) in self.buckets.iter().enumerate() {
+            cumulative += bucket_count;
+            if cumulative >= target {
+                return self.bucket_value(idx);
+            }
+        }
+        self.bucket_value(NUM_BUCKETS - 1)
+    }
+
+    /// Jitter, defined as p99 - p50, in microseconds. A large gap between
+    /// the typical case and the tail is what actually hurts a latency
+    /// sensitive strategy, even when the average looks fine.
+    pub fn jitter_us(&self) -> f64 {
+        self.quantile(0.99) - self.quantile(0.50)
+    }
+
+    /// Prints a percentile summary (p50/p90/p95/p99/p99.9 + jitter) for
+    /// this histogram under `label`, converting microseconds to
+    /// milliseconds for display.
+    pub fn print_summary(&self, label: &str) {
+        if self.count == 0 {
+            println!("{}: no samples", label);
+            return;
+        }
+        println!("{} (n={}):", label, self.count);
+        println!("  p50:    {:.3}ms", self.quantile(0.50) / 1000.0);
+        println!("  p90:    {:.3}ms", self.quantile(0.90) / 1000.0);
+        println!("  p95:    {:.3}ms", self.quantile(0.95) / 1000.0);
+        println!("  p99:    {:.3}ms", self.quantile(0.99) / 1000.0);
+        println!("  p99.9:  {:.3}ms", self.quantile(0.999) / 1000.0);
+        println!("  jitter: {:.3}ms (p99 - p50)", self.jitter_us() / 1000.0);
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_index_clamps_below_range() {
+        let hist = Histogram::new();
+        assert_eq!(hist.bucket_index(0.0), hist.bucket_index(MIN_US));
+        assert_eq!(hist.bucket_index(-100.0), hist.bucket_index(MIN_US));
+    }
+
+    #[test]
+    fn bucket_index_clamps_above_range() {
+        let hist = Histogram::new();
+        assert_eq!(hist.bucket_index(MAX_US * 10.0), NUM_BUCKETS - 1);
+    }
+
+    #[test]
+    fn bucket_index_is_monotonic() {
+        let hist = Histogram::new();
+        let mut prev = hist.bucket_index(MIN_US);
+        for value in [1.0, 10.0, 100.0, 1_000.0, 10_000.0, 100_000.0, 1_000_000.0] {
+            let idx = hist.bucket_index(value);
+            assert!(idx >= prev, "bucket index should not decrease as value grows");
+            prev = idx;
+        }
+    }
+
+    #[test]
+    fn quantile_on_empty_histogram_is_zero() {
+        let hist = Histogram::new();
+        assert_eq!(hist.quantile(0.0), 0.0);
+        assert_eq!(hist.quantile(0.5), 0.0);
+        assert_eq!(hist.quantile(1.0), 0.0);
+    }
+
+    #[test]
+    fn quantile_single_sample_returns_that_bucket() {
+        let mut hist = Histogram::new();
+        hist.record(1_000.0);
+        let p50 = hist.quantile(0.50);
+        let p99 = hist.quantile(0.99);
+        assert!((p50 - 1_000.0).abs() / 1_000.0 < 0.05);
+        assert_eq!(p50, p99);
+    }
+
+    #[test]
+    fn quantile_tracks_distribution() {
+        let mut hist = Histogram::new();
+        for _ in 0..99 {
+            hist.record(100.0);
+        }
+        hist.record(10_000.0);
+        let p50 = hist.quantile(0.50);
+        let p99 = hist.quantile(0.99);
+        let p999 = hist.quantile(0.999);
+        assert!((p50 - 100.0).abs() / 100.0 < 0.05);
+        // 99 of 100 samples sit at 100µs, so p99 is still in that bucket;
+        // only p99.9 reaches the single 10ms outlier.
+        assert!((p99 - 100.0).abs() / 100.0 < 0.05);
+        assert!((p999 - 10_000.0).abs() / 10_000.0 < 0.05);
+    }
+
+    #[test]
+    fn jitter_is_nonnegative_for_increasing_samples() {
+        let mut hist = Histogram::new();
+        for v in [50.0, 100.0, 150.0, 200.0, 5_000.0] {
+            hist.record(v);
+        }
+        assert!(hist.jitter_us() >= 0.0);
+    }
+}
@@ -0,0 +1,234 @@
+//! Optional persistence of per-run latency measurements to
+//! Postgres/TimescaleDB, so a VPS migration's effect on latency can be
+//! tracked across days rather than read once off stdout and discarded.
+//!
+//! Storage is entirely optional: if `PG_URL` isn't set in the
+//! environment, [`Storage::connect`] returns `Ok(None)` and callers skip
+//! persistence without any code-path changes.
+
+use anyhow::Result;
+use native_tls::TlsConnector;
+use postgres_native_tls::MakeTlsConnector;
+use std::env;
+use tokio::sync::mpsc;
+use tokio_postgres::NoTls;
+use uuid::Uuid;
+
+/// One order's latency breakdown, ready to persist.
+#[derive(Debug, Clone)]
+pub struct OrderRecord {
+    pub run_id: Uuid,
+    pub timestamp_ms: i64,
+    pub host: String,
+    pub token_id: String,
+    pub side: String,
+    pub build_ms: f64,
+    pub sign_ms: f64,
+    pub post_ms: f64,
+    pub total_ms: f64,
+}
+
+/// How many order rows to batch into a single INSERT before flushing.
+const BATCH_SIZE: usize = 25;
+
+/// A handle to an optional Postgres-backed store. Order rows are sent
+/// over an internal channel to a background task so that a slow DB
+/// write never adds latency to the order-placement loop; `finish`
+/// flushes any remainder and writes the per-run summary row.
+pub struct Storage {
+    tx: mpsc::UnboundedSender<OrderRecord>,
+    writer: tokio::task::JoinHandle<Result<()>>,
+    run_id: Uuid,
+}
+
+/// Connects to Postgres at `pg_url`, over TLS via `postgres-native-tls`
+/// when `use_ssl` is set, and spawns the connection's driver future so
+/// the caller can keep using the returned `Client` on its own.
+async fn connect_pg(pg_url: &str, use_ssl: bool) -> Result<tokio_postgres::Client> {
+    if use_ssl {
+        let connector = MakeTlsConnector::new(TlsConnector::new()?);
+        let (client, connection) = tokio_postgres::connect(pg_url, connector).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("⚠️  Postgres connection error: {}", e);
+            }
+        });
+        Ok(client)
+    } else {
+        let (client, connection) = tokio_postgres::connect(pg_url, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("⚠️  Postgres connection error: {}", e);
+            }
+        });
+        Ok(client)
+    }
+}
+
+impl Storage {
+    /// Connects using `PG_URL` (and optionally `PG_SSL=true` to require
+    /// TLS) from the environment. Returns `None` if `PG_URL` is unset,
+    /// in which case the caller should skip persistence entirely.
+    pub async fn connect(run_id: Uuid) -> Result<Option<Self>> {
+        let Ok(pg_url) = env::var("PG_URL") else {
+            return Ok(None);
+        };
+        let use_ssl = env::var("PG_SSL")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        let client = connect_pg(&pg_url, use_ssl).await?;
+
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS order_latencies (
+                    run_id UUID NOT NULL,
+                    ts TIMESTAMPTZ NOT NULL,
+                    host TEXT NOT NULL,
+                    token_id TEXT NOT NULL,
+                    side TEXT NOT NULL,
+                    build_ms DOUBLE PRECISION NOT NULL,
+                    sign_ms DOUBLE PRECISION NOT NULL,
+                    post_ms DOUBLE PRECISION NOT NULL,
+                    total_ms DOUBLE PRECISION NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS run_summaries (
+                    run_id UUID PRIMARY KEY,
+                    ts TIMESTAMPTZ NOT NULL,
+                    host TEXT NOT NULL,
+                    order_count INTEGER NOT NULL,
+                    p50_total_ms DOUBLE PRECISION NOT NULL,
+                    p99_total_ms DOUBLE PRECISION NOT NULL
+                );",
+            )
+            .await?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(Self::run_writer(client, rx));
+
+        Ok(Some(Self { tx, writer, run_id }))
+    }
+
+    /// Background task: batches incoming records and flushes them as a
+    /// single multi-row INSERT once `BATCH_SIZE` accumulate, or when the
+    /// channel closes. Order rows have no unique key and the INSERT has no
+    /// `ON CONFLICT` clause, so rows are only ever appended.
+    async fn run_writer(
+        client: tokio_postgres::Client,
+        mut rx: mpsc::UnboundedReceiver<OrderRecord>,
+    ) -> Result<()> {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        while let Some(record) = rx.recv().await {
+            batch.push(record);
+            if batch.len() >= BATCH_SIZE {
+                Self::flush_batch(&client, &batch).await?;
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            Self::flush_batch(&client, &batch).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes `batch` as a single multi-row INSERT rather than one
+    /// statement per order, so N orders cost one round trip.
+    async fn flush_batch(client: &tokio_postgres::Client, batch: &[OrderRecord]) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = String::from(
+            "INSERT INTO order_latencies \
+             (run_id, ts, host, token_id, side, build_ms, sign_ms, post_ms, total_ms) VALUES ",
+        );
+        let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::new();
+        let mut ts_values = Vec::with_capacity(batch.len());
+
+        for (i, record) in batch.iter().enumerate() {
+            if i > 0 {
+                query.push(',');
+            }
+            let base = i * 9;
+            query.push_str(&format!(
+                " (${}, to_timestamp(${}::double precision / 1000.0), ${}, ${}, ${}, ${}, ${}, ${}, ${})",
+                base + 1,
+                base + 2,
+                base + 3,
+                base + 4,
+                base + 5,
+                base + 6,
+                base + 7,
+                base + 8,
+                base + 9,
+            ));
+            ts_values.push(record.timestamp_ms as f64);
+        }
+
+        for (i, record) in batch.iter().enumerate() {
+            params.push(&record.run_id);
+            params.push(&ts_values[i]);
+            params.push(&record.host);
+            params.push(&record.token_id);
+            params.push(&record.side);
+            params.push(&record.build_ms);
+            params.push(&record.sign_ms);
+            params.push(&record.post_ms);
+            params.push(&record.total_ms);
+        }
+
+        client.execute(query.as_str(), &params).await?;
+        Ok(())
+    }
+
+    /// Queues one order's measurements for persistence. Returns
+    /// immediately; the actual write happens on the background writer
+    /// task and never blocks the order-placement loop.
+    pub fn record_order(&self, mut record: OrderRecord) {
+        record.run_id = self.run_id;
+        // The channel send is a cheap in-memory push; it does not wait
+        // on the database.
+        let _ = self.tx.send(record);
+    }
+
+    /// Flushes any remaining buffered orders, waits for the writer task
+    /// to finish, then writes the per-run summary row.
+    pub async fn finish(
+        self,
+        host: &str,
+        order_count: usize,
+        p50_total_ms: f64,
+        p99_total_ms: f64,
+    ) -> Result<()> {
+        drop(self.tx);
+        self.writer.await??;
+
+        // Reconnect briefly for the summary row; the writer task's
+        // client was moved into the background future and consumed.
+        let pg_url = env::var("PG_URL")?;
+        let use_ssl = env::var("PG_SSL")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let client = connect_pg(&pg_url, use_ssl).await?;
+
+        client
+            .execute(
+                "INSERT INTO run_summaries (run_id, ts, host, order_count, p50_total_ms, p99_total_ms) \
+                 VALUES ($1, now(), $2, $3, $4, $5) \
+                 ON CONFLICT (run_id) DO UPDATE SET \
+                 order_count = EXCLUDED.order_count, \
+                 p50_total_ms = EXCLUDED.p50_total_ms, \
+                 p99_total_ms = EXCLUDED.p99_total_ms",
+                &[
+                    &self.run_id,
+                    &host,
+                    &(order_count as i32),
+                    &p50_total_ms,
+                    &p99_total_ms,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+//! Concurrent throughput benchmark: drives N in-flight orders at once
+//! (bounded by a semaphore) instead of the strictly sequential
+//! one-order-then-sleep loop, so the test can find the point where the
+//! CLOB or connection pool saturates rather than only measuring
+//! single-order latency.
+
+use anyhow::Result;
+use polymarket_client_sdk::clob::types::{OrderType, Side};
+use polymarket_client_sdk::clob::Client;
+use polymarket_client_sdk::types::Decimal;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tokio::time::Duration;
+
+use alloy::primitives::U256;
+use alloy::signers::local::PrivateKeySigner;
+
+use crate::cleanup::PlacedOrders;
+use crate::market::{self, PriceMode};
+use crate::stats::Histogram;
+use crate::storage::{OrderRecord, Storage};
+
+/// What bounds a throughput run: a fixed number of orders, or a wall
+/// clock duration, whichever the caller configured.
+pub enum Target {
+    Count(usize),
+    Duration(Duration),
+}
+
+/// Result of one in-flight order task.
+struct OrderTiming {
+    order_id: String,
+    book_us: Option<f64>,
+    build_us: f64,
+    sign_us: f64,
+    post_us: f64,
+    total_us: f64,
+}
+
+/// Places one order, returning its per-stage timings. Permit is held for
+/// the task's lifetime via the caller and released on drop. If
+/// `price_mode` isn't [`PriceMode::Fixed`], fetches the live order book
+/// first and derives the order's price from it.
+#[allow(clippy::too_many_arguments)]
+async fn place_one(
+    client: &Client,
+    signer: &PrivateKeySigner,
+    token_id: U256,
+    price: Decimal,
+    size: Decimal,
+    side: Side,
+    price_mode: PriceMode,
+    tick_size: Decimal,
+    levels: usize,
+) -> Result<OrderTiming> {
+    let order_start = Instant::now();
+
+    let (order_price, book_us) = if let PriceMode::Fixed = price_mode {
+        (price, None)
+    } else {
+        let book_start = Instant::now();
+        let book = market::fetch_book(client, token_id, levels).await?;
+        let book_us = book_start.elapsed().as_micros() as f64;
+        let order_price = market::compute_price(price_mode, &book, side, price, tick_size);
+        (order_price, Some(book_us))
+    };
+
+    let build_start = Instant::now();
+    let limit_order = client
+        .limit_order()
+        .token_id(token_id)
+        .price(order_price)
+        .size(size)
+        .side(side)
+        .order_type(OrderType::GTC)
+        .build()
+        .await?;
+    let build_us = build_start.elapsed().as_micros() as f64;
+
+    let sign_start = Instant::now();
+    let signed_order = client.sign(signer, limit_order).await?;
+    let sign_us = sign_start.elapsed().as_micros() as f64;
+
+    let post_start = Instant::now();
+    let response = client.post_order(signed_order).await?;
+    let post_us = post_start.elapsed().as_micros() as f64;
+
+    let total_us = order_start.elapsed().as_micros() as f64;
+
+    Ok(OrderTiming {
+        order_id: response.order_id,
+        book_us,
+        build_us,
+        sign_us,
+        post_us,
+        total_us,
+    })
+}
+
+/// Runs the concurrent throughput benchmark. Keeps exactly `concurrency`
+/// orders outstanding via a bounded semaphore until `target` is reached,
+/// then reports achieved orders/sec plus the latency distribution
+/// observed under load.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    client: Arc<Client>,
+    signer: Arc<PrivateKeySigner>,
+    token_id: U256,
+    price: Decimal,
+    size: Decimal,
+    side: Side,
+    host: &str,
+    concurrency: usize,
+    target: Target,
+    storage: Option<Arc<Storage>>,
+    placed_orders: PlacedOrders,
+    price_mode: PriceMode,
+    tick_size: Decimal,
+    levels: usize,
+) -> Result<(Histogram, Histogram, Histogram, Histogram)> {
+    println!(
+        "\n--- 🏎️  Throughput Mode: {} concurrent, target={} ---",
+        concurrency,
+        match &target {
+            Target::Count(n) => format!("{} orders", n),
+            Target::Duration(d) => format!("{:.0}s", d.as_secs_f64()),
+        }
+    );
+
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = tokio::task::JoinSet::new();
+    let run_start = Instant::now();
+    let mut placed = 0usize;
+
+    let should_continue = |placed: usize, run_start: Instant| match &target {
+        Target::Count(n) => placed < *n,
+        Target::Duration(d) => run_start.elapsed() < *d,
+    };
+
+    while should_continue(placed, run_start) {
+        let permit = semaphore.clone().acquire_owned().await?;
+        let client = client.clone();
+        let signer = signer.clone();
+        let host = host.to_string();
+        let storage = storage.clone();
+
+        tasks.spawn(async move {
+            let _permit = permit;
+            let result = place_one(
+                &client, &signer, token_id, price, size, side, price_mode, tick_size, levels,
+            )
+            .await;
+            (host, result, storage)
+        });
+        placed += 1;
+    }
+
+    let mut total_hist = Histogram::new();
+    let mut build_hist = Histogram::new();
+    let mut sign_hist = Histogram::new();
+    let mut post_hist = Histogram::new();
+    let mut book_hist = Histogram::new();
+    let mut ok_count = 0usize;
+    let mut err_count = 0usize;
+
+    while let Some(joined) = tasks.join_next().await {
+        let (host, result, storage) = joined?;
+        match result {
+            Ok(timing) => {
+                ok_count += 1;
+                placed_orders.push(timing.order_id.clone());
+                total_hist.record(timing.total_us);
+                build_hist.record(timing.build_us);
+                sign_hist.record(timing.sign_us);
+                post_hist.record(timing.post_us);
+                if let Some(book_us) = timing.book_us {
+                    book_hist.record(book_us);
+                }
+
+                if let Some(storage) = storage {
+                    let timestamp_ms = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_millis() as i64)
+                        .unwrap_or(0);
+                    storage.record_order(OrderRecord {
+                        run_id: uuid::Uuid::nil(), // overwritten by Storage::record_order
+                        timestamp_ms,
+                        host,
+                        token_id: token_id.to_string(),
+                        side: format!("{:?}", side),
+                        build_ms: timing.build_us / 1000.0,
+                        sign_ms: timing.sign_us / 1000.0,
+                        post_ms: timing.post_us / 1000.0,
+                        total_ms: timing.total_us / 1000.0,
+                    });
+                }
+            }
+            Err(e) => {
+                err_count += 1;
+                eprintln!("⚠️  Order failed under load: {}", e);
+            }
+        }
+    }
+
+    let elapsed = run_start.elapsed().as_secs_f64();
+    let throughput = ok_count as f64 / elapsed;
+
+    println!("\n═══════════════════════════════════════════════════");
+    println!("📊 THROUGHPUT RESULTS");
+    println!("═══════════════════════════════════════════════════");
+    println!("Placed:     {} ok, {} failed", ok_count, err_count);
+    println!("Elapsed:    {:.2}s", elapsed);
+    println!("Throughput: {:.1} orders/sec", throughput);
+    // Total/build/sign/post latency distributions are printed by the
+    // caller's generic statistics section (labeled "under load"), so
+    // they aren't duplicated here.
+    if book_hist.count() > 0 {
+        println!();
+        book_hist.print_summary("Book Fetch Latency");
+    }
+
+    Ok((total_hist, build_hist, sign_hist, post_hist))
+}